@@ -1,5 +1,11 @@
+use futures::stream::{self, StreamExt};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
 use tokio::process::Command;
 use url::Url;
 use uuid::Uuid;
@@ -28,6 +34,32 @@ struct YtdlpMetadata {
     thumbnail: Option<String>,
 }
 
+/// A single entry from a flat-playlist listing
+#[derive(Debug, Deserialize)]
+struct PlaylistEntry {
+    id: String,
+    title: Option<String>,
+}
+
+/// Default number of playlist entries to download when no limit is given
+const DEFAULT_PLAYLIST_LIMIT: usize = 100;
+
+/// Default number of concurrent downloads for `download_youtube_batch`
+const DEFAULT_BATCH_PARALLELISM: usize = 8;
+
+/// GitHub release metadata, used to discover the latest yt-dlp build
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubAsset {
+    name: String,
+    size: u64,
+    browser_download_url: String,
+}
+
 /// Get the audio cache directory
 fn get_audio_dir() -> Result<PathBuf, String> {
     let cache_dir = dirs::cache_dir()
@@ -38,27 +70,182 @@ fn get_audio_dir() -> Result<PathBuf, String> {
     Ok(audio_dir)
 }
 
-/// Find yt-dlp executable
+/// Find a downloaded song's audio file regardless of its container extension
+/// (which varies with the requested `audio_format`)
+fn find_audio_file(audio_dir: &Path, song_id: &str) -> Option<PathBuf> {
+    std::fs::read_dir(audio_dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| path.file_stem().and_then(|s| s.to_str()) == Some(song_id))
+}
+
+/// Find yt-dlp executable, preferring our own bootstrapped install (kept
+/// current by `update_ytdlp`) over whatever's on PATH or in system dirs
 fn find_ytdlp() -> Option<String> {
-    let candidates = [
-        "yt-dlp",
-        "/usr/local/bin/yt-dlp",
-        "/opt/homebrew/bin/yt-dlp",
-        "/usr/bin/yt-dlp",
-    ];
+    let mut candidates = Vec::new();
+
+    if let Ok(bootstrapped) = ytdlp_bin_path() {
+        candidates.push(bootstrapped.to_string_lossy().to_string());
+    }
+
+    candidates.extend([
+        "yt-dlp".to_string(),
+        "/usr/local/bin/yt-dlp".to_string(),
+        "/opt/homebrew/bin/yt-dlp".to_string(),
+        "/usr/bin/yt-dlp".to_string(),
+    ]);
 
     for candidate in candidates {
-        if std::process::Command::new(candidate)
+        if std::process::Command::new(&candidate)
             .arg("--version")
             .output()
             .is_ok()
         {
-            return Some(candidate.to_string());
+            return Some(candidate);
         }
     }
     None
 }
 
+/// Path where a bootstrapped yt-dlp binary is cached
+fn ytdlp_bin_path() -> Result<PathBuf, String> {
+    let cache_dir = dirs::cache_dir()
+        .ok_or_else(|| "Could not find cache directory".to_string())?;
+    let bin_dir = cache_dir.join("autostepper").join("bin");
+    std::fs::create_dir_all(&bin_dir)
+        .map_err(|e| format!("Failed to create bin directory: {}", e))?;
+    let name = if cfg!(windows) { "yt-dlp.exe" } else { "yt-dlp" };
+    Ok(bin_dir.join(name))
+}
+
+/// Name of the yt-dlp release asset for the current platform and architecture
+fn ytdlp_asset_name() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "yt-dlp.exe"
+    } else if cfg!(target_os = "macos") {
+        "yt-dlp_macos"
+    } else if cfg!(target_arch = "aarch64") {
+        "yt-dlp_linux_aarch64"
+    } else {
+        "yt-dlp_linux"
+    }
+}
+
+/// Fetch the latest yt-dlp release metadata from GitHub
+async fn fetch_latest_ytdlp_release() -> Result<GithubRelease, String> {
+    let client = reqwest::Client::builder()
+        .user_agent("autostepper")
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+
+    client
+        .get("https://api.github.com/repos/yt-dlp/yt-dlp/releases/latest")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach GitHub: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("GitHub API error: {}", e))?
+        .json::<GithubRelease>()
+        .await
+        .map_err(|e| format!("Failed to parse GitHub release: {}", e))
+}
+
+/// Download a named asset's bytes from a release
+async fn download_release_asset(release: &GithubRelease, name: &str) -> Result<Vec<u8>, String> {
+    let asset = release
+        .assets
+        .iter()
+        .find(|a| a.name == name)
+        .ok_or_else(|| format!("Release has no asset named {}", name))?;
+
+    let bytes = reqwest::get(&asset.browser_download_url)
+        .await
+        .map_err(|e| format!("Failed to download {}: {}", name, e))?
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+
+    if bytes.len() as u64 != asset.size {
+        return Err(format!(
+            "Downloaded {} size mismatch: expected {} bytes, got {}",
+            name,
+            asset.size,
+            bytes.len()
+        ));
+    }
+
+    Ok(bytes.to_vec())
+}
+
+/// Verify a downloaded asset's SHA-256 digest against yt-dlp's published checksum file
+fn verify_sha256(bytes: &[u8], checksums: &str, asset_name: &str) -> Result<(), String> {
+    let expected = checksums
+        .lines()
+        .find_map(|line| {
+            let mut parts = line.split_whitespace();
+            let digest = parts.next()?;
+            let name = parts.next()?.trim_start_matches('*');
+            (name == asset_name).then(|| digest.to_string())
+        })
+        .ok_or_else(|| format!("No checksum entry for {}", asset_name))?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    let actual = format!("{:x}", hasher.finalize());
+
+    if actual != expected {
+        return Err(format!(
+            "Checksum mismatch for {}: expected {}, got {}",
+            asset_name, expected, actual
+        ));
+    }
+
+    Ok(())
+}
+
+/// Download, verify, and install the latest yt-dlp release, unconditionally
+async fn install_latest_ytdlp() -> Result<String, String> {
+    let release = fetch_latest_ytdlp_release().await?;
+    let asset_name = ytdlp_asset_name();
+
+    let bytes = download_release_asset(&release, asset_name).await?;
+    let checksums = download_release_asset(&release, "SHA2-256SUMS").await?;
+    verify_sha256(&bytes, &String::from_utf8_lossy(&checksums), asset_name)?;
+
+    let bin_path = ytdlp_bin_path()?;
+    std::fs::write(&bin_path, &bytes)
+        .map_err(|e| format!("Failed to write yt-dlp binary: {}", e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&bin_path, std::fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Failed to make yt-dlp executable: {}", e))?;
+    }
+
+    log::info!("Installed yt-dlp to {}", bin_path.display());
+
+    Ok(bin_path.to_string_lossy().to_string())
+}
+
+/// Find yt-dlp, downloading the latest GitHub release if it isn't already installed
+async fn ensure_ytdlp() -> Result<String, String> {
+    if let Some(path) = find_ytdlp() {
+        return Ok(path);
+    }
+
+    log::info!("yt-dlp not found, bootstrapping latest release from GitHub...");
+    install_latest_ytdlp().await
+}
+
+/// Force a fresh yt-dlp install, bypassing any system/PATH binary already found
+#[tauri::command]
+async fn update_ytdlp() -> Result<String, String> {
+    log::info!("Re-installing yt-dlp to pick up extractor fixes...");
+    install_latest_ytdlp().await
+}
+
 /// Find Deno executable
 fn find_deno() -> Option<String> {
     let home = dirs::home_dir()?;
@@ -160,11 +347,52 @@ fn validate_youtube_url(url: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Tunable yt-dlp extraction options for audio format, quality, size limit,
+/// and player client/PO-token overrides.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DownloadOptions {
+    #[serde(default = "default_audio_format")]
+    pub audio_format: String,
+    #[serde(default = "default_audio_quality")]
+    pub audio_quality: String,
+    #[serde(default = "default_max_filesize_mb")]
+    pub max_filesize_mb: u32,
+    #[serde(default)]
+    pub player_clients: Vec<String>,
+    #[serde(default)]
+    pub pot_token: Option<String>,
+}
+
+fn default_audio_format() -> String {
+    "mp3".to_string()
+}
+
+fn default_audio_quality() -> String {
+    "0".to_string()
+}
+
+fn default_max_filesize_mb() -> u32 {
+    50
+}
+
+impl Default for DownloadOptions {
+    fn default() -> Self {
+        Self {
+            audio_format: default_audio_format(),
+            audio_quality: default_audio_quality(),
+            max_filesize_mb: default_max_filesize_mb(),
+            player_clients: Vec::new(),
+            pot_token: None,
+        }
+    }
+}
+
 /// Build yt-dlp command with appropriate options
 fn build_ytdlp_args(
     base_args: &[&str],
     deno_path: Option<&str>,
     cookies_browser: Option<&str>,
+    options: Option<&DownloadOptions>,
 ) -> Vec<String> {
     let mut args: Vec<String> = base_args.iter().map(|s| s.to_string()).collect();
 
@@ -182,9 +410,214 @@ fn build_ytdlp_args(
         args.push(browser.to_string());
     }
 
+    // Add player client / PO token overrides for bot-detection evasion
+    if let Some(options) = options {
+        let mut youtube_args = Vec::new();
+        if !options.player_clients.is_empty() {
+            youtube_args.push(format!("player_client={}", options.player_clients.join(",")));
+        }
+        if let Some(token) = &options.pot_token {
+            youtube_args.push(format!("po_token={}", token));
+        }
+        if !youtube_args.is_empty() {
+            args.push("--extractor-args".to_string());
+            args.push(format!("youtube:{}", youtube_args.join(";")));
+        }
+    }
+
     args
 }
 
+/// Public Invidious instances tried in order as a yt-dlp fallback
+const INVIDIOUS_INSTANCES: &[&str] = &["yewtu.be", "invidious.nerdvpn.de", "inv.nadeko.net"];
+
+/// HTTP client with a short timeout so a dead Invidious instance fails fast
+/// instead of stalling the fallback loop
+fn invidious_http_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(8))
+        .connect_timeout(Duration::from_secs(5))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))
+}
+
+/// Video metadata and format list from the Invidious API
+#[derive(Debug, Deserialize)]
+struct InvidiousVideo {
+    title: String,
+    author: String,
+    #[serde(rename = "lengthSeconds")]
+    length_seconds: f64,
+    #[serde(rename = "videoThumbnails")]
+    video_thumbnails: Vec<InvidiousThumbnail>,
+    #[serde(rename = "adaptiveFormats")]
+    adaptive_formats: Vec<InvidiousFormat>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InvidiousThumbnail {
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct InvidiousFormat {
+    url: String,
+    #[serde(rename = "type")]
+    mime_type: String,
+    bitrate: Option<String>,
+}
+
+/// Extract the YouTube video ID from a watch/share URL
+fn extract_youtube_video_id(url: &str) -> Option<String> {
+    let parsed = Url::parse(url).ok()?;
+    let host = parsed.host_str()?;
+
+    if host.ends_with("youtu.be") {
+        return parsed.path_segments()?.next().map(|s| s.to_string());
+    }
+
+    parsed
+        .query_pairs()
+        .find(|(key, _)| key == "v")
+        .map(|(_, value)| value.to_string())
+}
+
+/// Pick the highest-bitrate audio-only format from an Invidious format list
+fn pick_best_audio_format(formats: &[InvidiousFormat]) -> Option<&InvidiousFormat> {
+    formats
+        .iter()
+        .filter(|f| f.mime_type.starts_with("audio/"))
+        .max_by_key(|f| {
+            f.bitrate
+                .as_deref()
+                .and_then(|b| b.parse::<u64>().ok())
+                .unwrap_or(0)
+        })
+}
+
+/// Fetch video metadata + format list from a single Invidious instance
+async fn fetch_invidious_video(instance: &str, video_id: &str) -> Result<InvidiousVideo, String> {
+    let url = format!("https://{}/api/v1/videos/{}", instance, video_id);
+    invidious_http_client()?
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| e.to_string())?
+        .json::<InvidiousVideo>()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Stream an audio-only format to disk and transcode it to mp3 via ffmpeg
+async fn stream_and_transcode(audio_url: &str, output_path: &Path) -> Result<(), String> {
+    let mut response = invidious_http_client()?
+        .get(audio_url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch audio stream: {}", e))?;
+
+    let tmp_path = output_path.with_extension("src");
+    let mut tmp_file = std::fs::File::create(&tmp_path)
+        .map_err(|e| format!("Failed to write temporary audio file: {}", e))?;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Failed to read audio stream: {}", e))?
+    {
+        std::io::Write::write_all(&mut tmp_file, &chunk)
+            .map_err(|e| format!("Failed to write temporary audio file: {}", e))?;
+    }
+    drop(tmp_file);
+
+    let status = Command::new("ffmpeg")
+        .args([
+            "-y",
+            "-i", tmp_path.to_str().unwrap(),
+            "-vn",
+            "-c:a", "libmp3lame",
+            output_path.to_str().unwrap(),
+        ])
+        .status()
+        .await
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+
+    let _ = std::fs::remove_file(&tmp_path);
+
+    if !status.success() {
+        return Err("ffmpeg transcoding failed".to_string());
+    }
+
+    Ok(())
+}
+
+/// Fall back to the public Invidious API when yt-dlp is blocked by bot
+/// detection and no browser cookies are available to retry with
+async fn download_via_invidious(
+    youtube_url: &str,
+    song_id: &str,
+    output_path: &Path,
+) -> Result<DownloadResponse, String> {
+    let video_id = extract_youtube_video_id(youtube_url)
+        .ok_or("Could not extract video ID for Invidious fallback")?;
+
+    if !has_ffmpeg() {
+        return Err("ffmpeg is required for the Invidious fallback".to_string());
+    }
+
+    for instance in INVIDIOUS_INSTANCES {
+        log::info!("Trying Invidious instance {} for {}", instance, video_id);
+
+        let video = match fetch_invidious_video(instance, &video_id).await {
+            Ok(video) => video,
+            Err(e) => {
+                log::warn!("Invidious instance {} failed: {}", instance, e);
+                continue;
+            }
+        };
+
+        let Some(format) = pick_best_audio_format(&video.adaptive_formats) else {
+            log::warn!("{} has no usable audio format, trying next instance", instance);
+            continue;
+        };
+
+        if let Err(e) = stream_and_transcode(&format.url, output_path).await {
+            log::warn!("Failed to fetch audio from {}: {}", instance, e);
+            continue;
+        }
+
+        let file_size = std::fs::metadata(output_path)
+            .map_err(|_| "Downloaded file not found")?
+            .len();
+
+        return Ok(DownloadResponse {
+            id: song_id.to_string(),
+            title: video.title,
+            artist: video.author,
+            duration: video.length_seconds,
+            thumbnail: video
+                .video_thumbnails
+                .into_iter()
+                .last()
+                .map(|t| t.url)
+                .unwrap_or_default(),
+            download_url: format!("autostepper://audio/{}", song_id),
+            file_size,
+        });
+    }
+
+    Err("All Invidious instances failed".to_string())
+}
+
+/// Check whether ffmpeg is available
+fn has_ffmpeg() -> bool {
+    std::process::Command::new("ffmpeg")
+        .arg("-version")
+        .output()
+        .is_ok()
+}
+
 /// Check if error indicates bot detection
 fn is_bot_detection_error(stderr: &str) -> bool {
     stderr.contains("Sign in to confirm")
@@ -197,14 +630,192 @@ async fn run_ytdlp(ytdlp: &str, args: &[String]) -> std::io::Result<std::process
     Command::new(ytdlp).args(args).output().await
 }
 
+/// Live progress update for an in-flight download, emitted to the webview
+#[derive(Debug, Clone, Serialize)]
+struct DownloadProgress {
+    #[serde(rename = "songId")]
+    song_id: String,
+    #[serde(rename = "downloadedBytes")]
+    downloaded_bytes: u64,
+    #[serde(rename = "totalBytes")]
+    total_bytes: u64,
+    #[serde(rename = "etaSecs")]
+    eta_secs: u64,
+    phase: String,
+}
+
+/// Parse a `--progress-template` line of the form `downloaded/total/eta` into a
+/// `DownloadProgress`, tolerating yt-dlp's "NA" placeholder for unknown fields
+fn parse_progress_line(line: &str, song_id: &str, phase: &str) -> Option<DownloadProgress> {
+    let mut fields = line.trim().splitn(3, '/');
+    let downloaded_bytes = fields.next()?.parse().ok()?;
+    let total_bytes = fields.next()?.parse().unwrap_or(0);
+    let eta_secs = fields.next()?.trim().parse().unwrap_or(0);
+
+    Some(DownloadProgress {
+        song_id: song_id.to_string(),
+        downloaded_bytes,
+        total_bytes,
+        eta_secs,
+        phase: phase.to_string(),
+    })
+}
+
+/// Run yt-dlp while emitting `download-progress` events parsed from its stdout
+async fn run_ytdlp_with_progress(
+    ytdlp: &str,
+    args: &[String],
+    song_id: &str,
+    phase: &str,
+    app_handle: &AppHandle,
+) -> std::io::Result<std::process::Output> {
+    let mut child = Command::new(ytdlp)
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    let stdout = child.stdout.take().expect("stdout was piped");
+    let mut stderr = child.stderr.take().expect("stderr was piped");
+
+    // Drain stdout and stderr concurrently - if yt-dlp fills the stderr pipe
+    // buffer (e.g. repeated fragment-retry warnings) while only stdout is
+    // being read, the child blocks on its stderr write and the download hangs
+    let stdout_task = async {
+        let mut lines = BufReader::new(stdout).lines();
+        let mut stdout_buf = Vec::new();
+        while let Some(line) = lines.next_line().await? {
+            if let Some(progress) = parse_progress_line(&line, song_id, phase) {
+                if let Err(e) = app_handle.emit("download-progress", progress) {
+                    log::warn!("Failed to emit download-progress: {}", e);
+                }
+            }
+            stdout_buf.extend_from_slice(line.as_bytes());
+            stdout_buf.push(b'\n');
+        }
+        std::io::Result::Ok(stdout_buf)
+    };
+
+    let stderr_task = async {
+        let mut stderr_buf = Vec::new();
+        stderr.read_to_end(&mut stderr_buf).await?;
+        std::io::Result::Ok(stderr_buf)
+    };
+
+    let (stdout_buf, stderr_buf) = tokio::try_join!(stdout_task, stderr_task)?;
+
+    let status = child.wait().await?;
+
+    Ok(std::process::Output {
+        status,
+        stdout: stdout_buf,
+        stderr: stderr_buf,
+    })
+}
+
 /// Download audio from YouTube
 #[tauri::command]
-async fn download_youtube(youtube_url: String) -> Result<DownloadResponse, String> {
+async fn download_youtube(
+    youtube_url: String,
+    embed_metadata: Option<bool>,
+    options: Option<DownloadOptions>,
+    app_handle: AppHandle,
+) -> Result<DownloadResponse, String> {
+    // Validate URL
+    validate_youtube_url(&youtube_url)?;
+
+    // Find yt-dlp
+    let ytdlp = ensure_ytdlp().await?;
+    let deno_path = find_deno();
+    let cookies_browser = find_browser_for_cookies();
+
+    if deno_path.is_none() && cookies_browser.is_none() {
+        log::warn!("Neither Deno nor browser cookies found - YouTube may block downloads");
+    }
+
+    download_single(
+        &youtube_url,
+        &ytdlp,
+        deno_path.as_deref(),
+        cookies_browser,
+        embed_metadata.unwrap_or(true),
+        &options.unwrap_or_default(),
+        &app_handle,
+    )
+    .await
+}
+
+/// Download a playlist or mix, returning one response per successfully downloaded track
+#[tauri::command]
+async fn download_youtube_playlist(
+    youtube_url: String,
+    limit: Option<usize>,
+    app_handle: AppHandle,
+) -> Result<Vec<DownloadResponse>, String> {
     // Validate URL
     validate_youtube_url(&youtube_url)?;
 
     // Find yt-dlp
-    let ytdlp = find_ytdlp().ok_or("yt-dlp not found. Install with: pip install -U yt-dlp")?;
+    let ytdlp = ensure_ytdlp().await?;
+    let deno_path = find_deno();
+    let cookies_browser = find_browser_for_cookies();
+
+    if deno_path.is_none() && cookies_browser.is_none() {
+        log::warn!("Neither Deno nor browser cookies found - YouTube may block downloads");
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_PLAYLIST_LIMIT);
+
+    log::info!("Enumerating playlist: {}", youtube_url);
+    let entries =
+        fetch_playlist_entries(&ytdlp, &youtube_url, deno_path.as_deref(), cookies_browser)
+            .await?;
+
+    log::info!(
+        "Playlist has {} entries, downloading up to {}",
+        entries.len(),
+        limit
+    );
+
+    let mut results = Vec::new();
+    for entry in entries.into_iter().take(limit) {
+        let entry_url = format!("https://www.youtube.com/watch?v={}", entry.id);
+        match download_single(
+            &entry_url,
+            &ytdlp,
+            deno_path.as_deref(),
+            cookies_browser,
+            true,
+            &DownloadOptions::default(),
+            &app_handle,
+        )
+        .await
+        {
+            Ok(response) => results.push(response),
+            Err(e) => {
+                log::warn!(
+                    "Skipping playlist entry {} ({}): {}",
+                    entry.id,
+                    entry.title.as_deref().unwrap_or("untitled"),
+                    e
+                );
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+/// Download many URLs concurrently with a bounded worker pool, resolving the
+/// yt-dlp/Deno/cookies probes once up front and sharing them across all workers.
+/// One failing URL doesn't abort the rest of the batch.
+#[tauri::command]
+async fn download_youtube_batch(
+    urls: Vec<String>,
+    parallel: Option<usize>,
+    app_handle: AppHandle,
+) -> Result<Vec<Result<DownloadResponse, String>>, String> {
+    let ytdlp = ensure_ytdlp().await?;
     let deno_path = find_deno();
     let cookies_browser = find_browser_for_cookies();
 
@@ -212,21 +823,119 @@ async fn download_youtube(youtube_url: String) -> Result<DownloadResponse, Strin
         log::warn!("Neither Deno nor browser cookies found - YouTube may block downloads");
     }
 
+    let parallel = parallel.unwrap_or(DEFAULT_BATCH_PARALLELISM).max(1);
+
+    log::info!("Downloading {} URLs with parallelism {}", urls.len(), parallel);
+
+    let results = stream::iter(urls)
+        .map(|url| {
+            let ytdlp = ytdlp.clone();
+            let deno_path = deno_path.clone();
+            let app_handle = app_handle.clone();
+            async move {
+                validate_youtube_url(&url)?;
+                download_single(
+                    &url,
+                    &ytdlp,
+                    deno_path.as_deref(),
+                    cookies_browser,
+                    true,
+                    &DownloadOptions::default(),
+                    &app_handle,
+                )
+                .await
+            }
+        })
+        .buffer_unordered(parallel)
+        .collect::<Vec<Result<DownloadResponse, String>>>()
+        .await;
+
+    Ok(results)
+}
+
+/// List the entries of a playlist/mix URL without downloading them, retrying with
+/// browser cookies if bot detection blocks the initial attempt
+async fn fetch_playlist_entries(
+    ytdlp: &str,
+    youtube_url: &str,
+    deno_path: Option<&str>,
+    cookies_browser: Option<&str>,
+) -> Result<Vec<PlaylistEntry>, String> {
+    let list_base_args: Vec<&str> = vec![
+        "--yes-playlist",
+        "--flat-playlist",
+        "--dump-json",
+        "--no-warnings",
+        youtube_url,
+    ];
+
+    let list_args = build_ytdlp_args(&list_base_args, deno_path, None, None);
+    let output = run_ytdlp(ytdlp, &list_args)
+        .await
+        .map_err(|e| format!("Failed to list playlist: {}", e))?;
+
+    let output = if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if is_bot_detection_error(&stderr) && cookies_browser.is_some() {
+            log::warn!("Bot detection triggered during playlist listing, retrying with browser cookies...");
+            let list_args_with_cookies =
+                build_ytdlp_args(&list_base_args, deno_path, cookies_browser, None);
+            run_ytdlp(ytdlp, &list_args_with_cookies)
+                .await
+                .map_err(|e| format!("Failed to list playlist with cookies: {}", e))?
+        } else {
+            output
+        }
+    } else {
+        output
+    };
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("yt-dlp playlist listing error: {}", stderr));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let entries = stdout
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str::<PlaylistEntry>(line).ok())
+        .collect();
+
+    Ok(entries)
+}
+
+/// Download a single video, given already-resolved yt-dlp/Deno/cookies paths
+async fn download_single(
+    youtube_url: &str,
+    ytdlp: &str,
+    deno_path: Option<&str>,
+    cookies_browser: Option<&str>,
+    embed_metadata: bool,
+    options: &DownloadOptions,
+    app_handle: &AppHandle,
+) -> Result<DownloadResponse, String> {
     // Generate unique ID
     let song_id = Uuid::new_v4().to_string();
     let audio_dir = get_audio_dir()?;
-    let output_path = audio_dir.join(format!("{}.mp3", song_id));
+    // yt-dlp's audio-extraction postprocessor renames the final file to match
+    // --audio-format, not whatever extension is baked into the -o template,
+    // so the two must agree here.
+    let output_path = audio_dir.join(format!("{}.{}", song_id, options.audio_format));
+    // The Invidious fallback always transcodes to mp3 via ffmpeg directly, so
+    // it gets its own fixed-extension path regardless of `options.audio_format`.
+    let invidious_output_path = audio_dir.join(format!("{}.mp3", song_id));
 
     log::info!("Fetching metadata for: {}", youtube_url);
 
     // Try to get metadata - first with Deno only, then with cookies fallback
-    let metadata_base_args = vec!["--dump-json", "--no-download", youtube_url.as_str()];
+    let metadata_base_args = vec!["--dump-json", "--no-download", youtube_url];
 
     // First attempt: Deno only (if available)
-    let metadata_args = build_ytdlp_args(&metadata_base_args, deno_path.as_deref(), None);
+    let metadata_args = build_ytdlp_args(&metadata_base_args, deno_path, None, Some(options));
     log::info!("Trying metadata fetch with Deno...");
 
-    let metadata_output = run_ytdlp(&ytdlp, &metadata_args)
+    let metadata_output = run_ytdlp(ytdlp, &metadata_args)
         .await
         .map_err(|e| format!("Failed to run yt-dlp: {}", e))?;
 
@@ -237,10 +946,11 @@ async fn download_youtube(youtube_url: String) -> Result<DownloadResponse, Strin
             log::warn!("Bot detection triggered, retrying with browser cookies...");
             let metadata_args_with_cookies = build_ytdlp_args(
                 &metadata_base_args,
-                deno_path.as_deref(),
+                deno_path,
                 cookies_browser,
+                Some(options),
             );
-            run_ytdlp(&ytdlp, &metadata_args_with_cookies)
+            run_ytdlp(ytdlp, &metadata_args_with_cookies)
                 .await
                 .map_err(|e| format!("Failed to run yt-dlp with cookies: {}", e))?
         } else {
@@ -253,12 +963,18 @@ async fn download_youtube(youtube_url: String) -> Result<DownloadResponse, Strin
     if !metadata_output.status.success() {
         let stderr = String::from_utf8_lossy(&metadata_output.stderr);
         if is_bot_detection_error(&stderr) {
-            let hint = if cookies_browser.is_some() {
-                "Browser cookies didn't help. Try logging into YouTube in your browser and try again."
-            } else {
-                "Install Deno (https://deno.land) or log into YouTube in Chrome/Firefox."
-            };
-            return Err(format!("YouTube bot detection triggered. {}", hint));
+            if cookies_browser.is_none() {
+                log::warn!(
+                    "yt-dlp blocked by bot detection and no browser cookies available, \
+                     falling back to Invidious..."
+                );
+                return download_via_invidious(youtube_url, &song_id, &invidious_output_path).await;
+            }
+            return Err(
+                "YouTube bot detection triggered. Browser cookies didn't help. \
+                 Try logging into YouTube in your browser and try again."
+                    .to_string(),
+            );
         }
         return Err(format!("yt-dlp error: {}", stderr));
     }
@@ -271,20 +987,38 @@ async fn download_youtube(youtube_url: String) -> Result<DownloadResponse, Strin
     // Download audio - use same strategy (Deno first, cookies fallback)
     log::info!("Downloading audio...");
 
-    let download_base_args: Vec<&str> = vec![
+    let max_filesize_arg = format!("{}m", options.max_filesize_mb);
+
+    let mut download_base_args: Vec<&str> = vec![
         "-x",
-        "--audio-format", "mp3",
-        "--audio-quality", "0",
+        "--audio-format", &options.audio_format,
+        "--audio-quality", &options.audio_quality,
         "--no-playlist",
-        "--max-filesize", "50m",
-        "-o", output_path.to_str().unwrap(),
-        &youtube_url,
+        "--max-filesize", &max_filesize_arg,
+        "--newline",
+        "--progress-template",
+        "%(progress.downloaded_bytes)s/%(progress.total_bytes)s/%(progress.eta)s",
     ];
 
+    // Embedding thumbnails/metadata requires ffmpeg; fall back gracefully so
+    // downloads never hard-fail on tagging
+    if embed_metadata && has_ffmpeg() {
+        download_base_args.extend([
+            "--embed-thumbnail",
+            "--embed-metadata",
+            "--add-metadata",
+            "--convert-thumbnails", "jpg",
+        ]);
+    } else if embed_metadata {
+        log::warn!("ffmpeg not found, skipping thumbnail/metadata embedding");
+    }
+
+    download_base_args.extend(["-o", output_path.to_str().unwrap(), youtube_url]);
+
     // First attempt: Deno only
-    let download_args = build_ytdlp_args(&download_base_args, deno_path.as_deref(), None);
+    let download_args = build_ytdlp_args(&download_base_args, deno_path, None, Some(options));
 
-    let download_output = run_ytdlp(&ytdlp, &download_args)
+    let download_output = run_ytdlp_with_progress(ytdlp, &download_args, &song_id, "downloading", app_handle)
         .await
         .map_err(|e| format!("Failed to download: {}", e))?;
 
@@ -295,10 +1029,11 @@ async fn download_youtube(youtube_url: String) -> Result<DownloadResponse, Strin
             log::warn!("Bot detection on download, retrying with browser cookies...");
             let download_args_with_cookies = build_ytdlp_args(
                 &download_base_args,
-                deno_path.as_deref(),
+                deno_path,
                 cookies_browser,
+                Some(options),
             );
-            run_ytdlp(&ytdlp, &download_args_with_cookies)
+            run_ytdlp_with_progress(ytdlp, &download_args_with_cookies, &song_id, "downloading", app_handle)
                 .await
                 .map_err(|e| format!("Failed to download with cookies: {}", e))?
         } else {
@@ -310,6 +1045,13 @@ async fn download_youtube(youtube_url: String) -> Result<DownloadResponse, Strin
 
     if !download_output.status.success() {
         let stderr = String::from_utf8_lossy(&download_output.stderr);
+        if is_bot_detection_error(&stderr) && cookies_browser.is_none() {
+            log::warn!(
+                "yt-dlp download blocked by bot detection and no browser cookies available, \
+                 falling back to Invidious..."
+            );
+            return download_via_invidious(youtube_url, &song_id, &invidious_output_path).await;
+        }
         return Err(format!("Download failed: {}", stderr));
     }
 
@@ -337,11 +1079,7 @@ async fn download_youtube(youtube_url: String) -> Result<DownloadResponse, Strin
 #[tauri::command]
 fn get_audio_path(song_id: String) -> Result<String, String> {
     let audio_dir = get_audio_dir()?;
-    let path = audio_dir.join(format!("{}.mp3", song_id));
-
-    if !path.exists() {
-        return Err("Audio file not found".to_string());
-    }
+    let path = find_audio_file(&audio_dir, &song_id).ok_or("Audio file not found")?;
 
     Ok(path.to_string_lossy().to_string())
 }
@@ -350,7 +1088,7 @@ fn get_audio_path(song_id: String) -> Result<String, String> {
 #[tauri::command]
 fn read_audio_file(song_id: String) -> Result<Vec<u8>, String> {
     let audio_dir = get_audio_dir()?;
-    let path = audio_dir.join(format!("{}.mp3", song_id));
+    let path = find_audio_file(&audio_dir, &song_id).ok_or("Audio file not found")?;
 
     std::fs::read(&path)
         .map_err(|e| format!("Failed to read audio file: {}", e))
@@ -362,7 +1100,7 @@ fn read_audio_file_base64(song_id: String) -> Result<String, String> {
     use base64::{Engine as _, engine::general_purpose::STANDARD};
 
     let audio_dir = get_audio_dir()?;
-    let path = audio_dir.join(format!("{}.mp3", song_id));
+    let path = find_audio_file(&audio_dir, &song_id).ok_or("Audio file not found")?;
 
     let bytes = std::fs::read(&path)
         .map_err(|e| format!("Failed to read audio file: {}", e))?;
@@ -376,12 +1114,7 @@ fn check_dependencies() -> serde_json::Value {
     let ytdlp = find_ytdlp();
     let deno = find_deno();
     let browser = find_browser_for_cookies();
-
-    // Check ffmpeg
-    let ffmpeg = std::process::Command::new("ffmpeg")
-        .arg("-version")
-        .output()
-        .is_ok();
+    let ffmpeg = has_ffmpeg();
 
     serde_json::json!({
         "ytdlp": ytdlp.is_some(),
@@ -401,10 +1134,9 @@ fn cleanup_audio() -> Result<u32, String> {
 
     if let Ok(entries) = std::fs::read_dir(&audio_dir) {
         for entry in entries.flatten() {
-            if entry.path().extension().map(|e| e == "mp3").unwrap_or(false) {
-                if std::fs::remove_file(entry.path()).is_ok() {
-                    count += 1;
-                }
+            let path = entry.path();
+            if path.is_file() && std::fs::remove_file(&path).is_ok() {
+                count += 1;
             }
         }
     }
@@ -420,6 +1152,9 @@ pub fn run() {
             .build())
         .invoke_handler(tauri::generate_handler![
             download_youtube,
+            download_youtube_playlist,
+            download_youtube_batch,
+            update_ytdlp,
             get_audio_path,
             read_audio_file,
             read_audio_file_base64,
@@ -429,3 +1164,105 @@ pub fn run() {
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_progress_line_parses_complete_fields() {
+        let progress = parse_progress_line("1024/2048/30", "song-1", "downloading").unwrap();
+        assert_eq!(progress.song_id, "song-1");
+        assert_eq!(progress.downloaded_bytes, 1024);
+        assert_eq!(progress.total_bytes, 2048);
+        assert_eq!(progress.eta_secs, 30);
+        assert_eq!(progress.phase, "downloading");
+    }
+
+    #[test]
+    fn parse_progress_line_tolerates_na_placeholders() {
+        let progress = parse_progress_line("1024/NA/NA", "song-1", "downloading").unwrap();
+        assert_eq!(progress.downloaded_bytes, 1024);
+        assert_eq!(progress.total_bytes, 0);
+        assert_eq!(progress.eta_secs, 0);
+    }
+
+    #[test]
+    fn parse_progress_line_rejects_unparseable_lines() {
+        assert!(parse_progress_line("not a progress line", "song-1", "downloading").is_none());
+    }
+
+    #[test]
+    fn verify_sha256_accepts_matching_digest() {
+        let bytes = b"yt-dlp binary contents";
+        let mut hasher = Sha256::new();
+        hasher.update(bytes);
+        let digest = format!("{:x}", hasher.finalize());
+        let checksums = format!("{}  yt-dlp_linux\n", digest);
+
+        assert!(verify_sha256(bytes, &checksums, "yt-dlp_linux").is_ok());
+    }
+
+    #[test]
+    fn verify_sha256_rejects_mismatched_digest() {
+        let checksums = "0000000000000000000000000000000000000000000000000000000000000000  yt-dlp_linux\n";
+        assert!(verify_sha256(b"yt-dlp binary contents", checksums, "yt-dlp_linux").is_err());
+    }
+
+    #[test]
+    fn verify_sha256_errors_when_asset_missing_from_checksums() {
+        let checksums = "deadbeef  some-other-asset\n";
+        assert!(verify_sha256(b"contents", checksums, "yt-dlp_linux").is_err());
+    }
+
+    #[test]
+    fn extract_youtube_video_id_from_short_url() {
+        assert_eq!(
+            extract_youtube_video_id("https://youtu.be/dQw4w9WgXcQ"),
+            Some("dQw4w9WgXcQ".to_string())
+        );
+    }
+
+    #[test]
+    fn extract_youtube_video_id_from_watch_url() {
+        assert_eq!(
+            extract_youtube_video_id("https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=10s"),
+            Some("dQw4w9WgXcQ".to_string())
+        );
+    }
+
+    #[test]
+    fn extract_youtube_video_id_rejects_invalid_url() {
+        assert!(extract_youtube_video_id("not a url").is_none());
+        assert!(extract_youtube_video_id("https://example.com/").is_none());
+    }
+
+    #[test]
+    fn pick_best_audio_format_prefers_highest_bitrate_audio() {
+        let formats = vec![
+            InvidiousFormat {
+                url: "a".to_string(),
+                mime_type: "audio/webm".to_string(),
+                bitrate: Some("128000".to_string()),
+            },
+            InvidiousFormat {
+                url: "b".to_string(),
+                mime_type: "audio/webm".to_string(),
+                bitrate: Some("256000".to_string()),
+            },
+            InvidiousFormat {
+                url: "c".to_string(),
+                mime_type: "video/mp4".to_string(),
+                bitrate: Some("5000000".to_string()),
+            },
+        ];
+
+        let best = pick_best_audio_format(&formats).unwrap();
+        assert_eq!(best.url, "b");
+    }
+
+    #[test]
+    fn pick_best_audio_format_returns_none_for_empty_list() {
+        assert!(pick_best_audio_format(&[]).is_none());
+    }
+}